@@ -2,7 +2,7 @@
 //!
 //! `FdLock` is a Rust crate that provides functionality for file locking using `flock` or `fcntl` operations.
 //!
-//! This crate defines a trait `FdLock` that extends the `AsRawFd` trait,
+//! This crate defines a trait `FdLock` that extends the `AsRawFd`/`AsRawHandle` trait,
 //! allowing file locks to be placed on file descriptors. It supports both
 //! shared and exclusive locks, as well as unlocking operations.
 //!
@@ -10,12 +10,18 @@
 //!
 //! Placing a shared lock on a file:
 //!
+//! `std::fs::File` has its own inherent `lock_shared`/`try_lock_shared`/`unlock`
+//! methods (stabilized after this crate's `FdLock::lock_shared` et al. were
+//! written), which take priority over a trait method of the same name in plain
+//! method-call syntax. Call `FdLock`'s versions through the trait explicitly to
+//! make sure it's this crate's `flock`/`LockFileEx` backend that runs, not std's:
+//!
 //! ```no_run
 //! use filelock_rs::FdLock;
 //! use std::fs::File;
 //!
 //! let file = File::open("data.txt").expect("Failed to open file");
-//! let lock_result = file.lock_shared();
+//! let lock_result = FdLock::lock_shared(&file);
 //!
 //! match lock_result {
 //!     Ok(()) => {
@@ -55,156 +61,194 @@
 //!
 //! ## Notes
 //!
-//! - The behavior of file locking may differ depending on the operating system.
-//! - The crate uses the `libc` and `io::Result` types from the standard library.
+//! - This crate works on both Unix (via `flock`/`fcntl`) and Windows (via `LockFileEx`/`UnlockFile`).
+//! - The crate uses the `libc`/`windows-sys` crates and `io::Result` types from the standard library.
 //! - If the file lock operation fails, an `io::Error` is returned.
+//! - Going cross-platform removed two previously-public items that couldn't be made to
+//!   mean the same thing on both OSes: the raw `FdLock::flock(&self, operation)` method and
+//!   the `Operation` type alias (`libc::c_int`) it took. `lock_shared`/`lock_exclusive`/
+//!   `try_lock_shared`/`try_lock_exclusive`/`unlock` remain as the public, portable surface.
+#[cfg(target_os = "linux")]
+pub mod ofd;
 pub mod pid;
+#[cfg(unix)]
+pub mod process_locker;
+#[cfg(unix)]
+pub mod record;
+pub mod rwlock;
+mod sys;
 
+use std::fmt;
 use std::io;
-use std::os::fd::AsRawFd;
 
-/// FdLock Operation type.
-pub type Operation = libc::c_int;
+/// Error returned by the non-blocking `try_lock_shared`/`try_lock_exclusive` methods
+/// on [`FdLock`], distinguishing "someone else holds the lock" from a genuine I/O failure.
+#[derive(Debug)]
+pub enum TryLockError {
+    /// The lock is currently held by someone else; the caller may retry later.
+    WouldBlock,
+    /// Some other I/O error occurred while trying to take the lock.
+    Io(io::Error),
+}
+
+impl fmt::Display for TryLockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryLockError::WouldBlock => write!(f, "lock is held by another process"),
+            TryLockError::Io(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for TryLockError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TryLockError::WouldBlock => None,
+            TryLockError::Io(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for TryLockError {
+    fn from(err: io::Error) -> Self {
+        if err.kind() == io::ErrorKind::WouldBlock {
+            TryLockError::WouldBlock
+        } else {
+            TryLockError::Io(err)
+        }
+    }
+}
 
-/// Place a shared lock. More than one process may hold a shared lock for a given file at a given time.
-#[allow(dead_code)]
-const LOCK_SH: Operation = libc::LOCK_SH;
-/// Place an exclusive lock. Only one process may hold an exclusive lock for a given file at a given time.
-#[allow(dead_code)]
-const LOCK_EX: Operation = libc::LOCK_EX;
-/// Remove an existing lock held by this process.
-#[allow(dead_code)]
-const LOCK_UN: Operation = libc::LOCK_UN;
+impl From<TryLockError> for io::Error {
+    fn from(err: TryLockError) -> Self {
+        match err {
+            TryLockError::WouldBlock => {
+                io::Error::new(io::ErrorKind::WouldBlock, "lock is held by another process")
+            }
+            TryLockError::Io(err) => err,
+        }
+    }
+}
 
 /// The `FdLock` trait extends the `AsRawFd` trait, allowing
 /// file locks to be placed on file descriptors.
-pub trait FdLock: AsRawFd {
-    /// Places a file lock on the associated file descriptor using the `flock` operation.
+///
+/// `std::fs::File` also has its own inherent `lock_shared`/`try_lock_shared`/`unlock`
+/// methods; those take priority over this trait's same-named methods in plain
+/// `file.lock_shared()`-style calls. Use fully-qualified syntax (`FdLock::lock_shared(&file)`)
+/// to be sure you're calling this crate's implementation rather than std's.
+#[cfg(unix)]
+pub trait FdLock: std::os::fd::AsRawFd {
+    /// Places a shared lock on the file.
     ///
-    /// # Arguments
+    /// # Errors
     ///
-    /// * `operation`: The type of lock to place on the file.
+    /// If the lock operation fails, an `io::Error` is returned.
+    fn lock_shared(&self) -> io::Result<()> {
+        sys::lock_shared(self.as_raw_fd())
+    }
+
+    /// Places an exclusive lock on the file.
     ///
     /// # Errors
     ///
     /// If the lock operation fails, an `io::Error` is returned.
+    fn lock_exclusive(&self) -> io::Result<()> {
+        sys::lock_exclusive(self.as_raw_fd())
+    }
+
+    /// Tries to place a shared lock on the file without blocking.
     ///
-    #[cfg(not(target_os = "solaris"))]
-    fn flock(&self, operation: Operation) -> io::Result<()> {
-        let ret = unsafe { libc::flock(self.as_raw_fd(), operation) };
-        if ret < 0 {
-            return Err(io::Error::last_os_error());
-        }
+    /// # Errors
+    ///
+    /// Returns [`TryLockError::WouldBlock`] if the lock is currently held by someone
+    /// else, or [`TryLockError::Io`] if the lock operation otherwise fails.
+    fn try_lock_shared(&self) -> Result<(), TryLockError> {
+        sys::try_lock_shared(self.as_raw_fd())?;
         Ok(())
     }
 
-    /// Places a file lock on the associated file descriptor using the `flock` operation.
+    /// Tries to place an exclusive lock on the file without blocking.
     ///
-    /// # Arguments
+    /// # Errors
     ///
-    /// * `operation`: The type of lock to place on the file.
+    /// Returns [`TryLockError::WouldBlock`] if the lock is currently held by someone
+    /// else, or [`TryLockError::Io`] if the lock operation otherwise fails.
+    fn try_lock_exclusive(&self) -> Result<(), TryLockError> {
+        sys::try_lock_exclusive(self.as_raw_fd())?;
+        Ok(())
+    }
+
+    /// Unlocks the file.
     ///
-    /// # Errors
+    /// This method removes the lock held by the current process on the associated file descriptor.
     ///
-    /// If the lock operation fails, an `io::Error` is returned.
+    /// # Errors
     ///
-    #[cfg(target_os = "solaris")]
-    fn flock(&self, operation: Operation) -> io::Result<()> {
-        // Solaris lacks flock(), so try to emulate using fcntl()
-        let mut flock = libc::flock {
-            l_type: 0,
-            l_whence: 0,
-            l_start: 0,
-            l_len: 0,
-            l_sysid: 0,
-            l_pid: 0,
-            l_pad: [0, 0, 0, 0],
-        };
-        flock.l_type = if operation & LOCK_UN != 0 {
-            LOCK_UN
-        } else if operation & LOCK_EX != 0 {
-            libc::F_WRLCK
-        } else if operation & LOCK_SH != 0 {
-            libc::F_RDLCK
-        } else {
-            return Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("unexpected flock() operation"),
-            ));
-        };
-
-        let mut cmd = libc::F_SETLKW;
-        if (flag & libc::LOCK_NB) != 0 {
-            cmd = libc::F_SETLK;
-        }
-
-        let ret = unsafe { libc::fcntl(file.as_raw_fd(), cmd, &flock) };
-        if ret < 0 {
-            Err(Error::last_os_error())
-        } else {
-            Ok(())
-        }
+    /// If the unlock operation fails, an `io::Error` is returned.
+    fn unlock(&self) -> io::Result<()> {
+        sys::unlock(self.as_raw_fd())
     }
+}
 
+/// The `FdLock` trait extends the `AsRawHandle` trait, allowing
+/// file locks to be placed on file handles.
+///
+/// `std::fs::File` also has its own inherent `lock_shared`/`try_lock_shared`/`unlock`
+/// methods; those take priority over this trait's same-named methods in plain
+/// `file.lock_shared()`-style calls. Use fully-qualified syntax (`FdLock::lock_shared(&file)`)
+/// to be sure you're calling this crate's implementation rather than std's.
+#[cfg(windows)]
+pub trait FdLock: std::os::windows::io::AsRawHandle {
     /// Places a shared lock on the file.
     ///
-    /// This method uses the `LOCK_SH` operation to place a shared lock on the associated file descriptor.
-    ///
     /// # Errors
     ///
     /// If the lock operation fails, an `io::Error` is returned.
-    ///
     fn lock_shared(&self) -> io::Result<()> {
-        self.flock(libc::LOCK_SH)
+        sys::lock_shared(self.as_raw_handle())
     }
 
     /// Places an exclusive lock on the file.
     ///
-    /// This method uses the `LOCK_EX` operation to place an exclusive lock on the associated file descriptor.
-    ///
     /// # Errors
     ///
     /// If the lock operation fails, an `io::Error` is returned.
-    ///
     fn lock_exclusive(&self) -> io::Result<()> {
-        self.flock(libc::LOCK_EX)
+        sys::lock_exclusive(self.as_raw_handle())
     }
 
-    /// Tries to place a shared lock on the file.
-    ///
-    /// This method uses the `LOCK_SH | LOCK_NB` operations to try placing a shared lock on the associated file descriptor.
+    /// Tries to place a shared lock on the file without blocking.
     ///
     /// # Errors
     ///
-    /// If the lock operation fails or the lock is not immediately available, an `io::Error` is returned.
-    ///
-    fn try_lock_shared(&self) -> io::Result<()> {
-        self.flock(libc::LOCK_SH | libc::LOCK_NB)
+    /// Returns [`TryLockError::WouldBlock`] if the lock is currently held by someone
+    /// else, or [`TryLockError::Io`] if the lock operation otherwise fails.
+    fn try_lock_shared(&self) -> Result<(), TryLockError> {
+        sys::try_lock_shared(self.as_raw_handle())?;
+        Ok(())
     }
 
-    /// Tries to place an exclusive lock on the file.
-    ///
-    /// This method uses the `LOCK_EX | LOCK_NB` operations to try placing an exclusive lock on the associated file descriptor.
+    /// Tries to place an exclusive lock on the file without blocking.
     ///
     /// # Errors
     ///
-    /// If the lock operation fails or the lock is not immediately available, an `io::Error` is returned.
-    ///
-    fn try_lock_exclusive(&self) -> io::Result<()> {
-        self.flock(libc::LOCK_EX | libc::LOCK_NB)
+    /// Returns [`TryLockError::WouldBlock`] if the lock is currently held by someone
+    /// else, or [`TryLockError::Io`] if the lock operation otherwise fails.
+    fn try_lock_exclusive(&self) -> Result<(), TryLockError> {
+        sys::try_lock_exclusive(self.as_raw_handle())?;
+        Ok(())
     }
 
     /// Unlocks the file.
     ///
-    /// This method removes the lock held by the current process on the associated file descriptor.
-    /// It uses the `LOCK_UN` operation to unlock the file.
+    /// This method removes the lock held by the current process on the associated file handle.
     ///
     /// # Errors
     ///
     /// If the unlock operation fails, an `io::Error` is returned.
-    ///
     fn unlock(&self) -> io::Result<()> {
-        self.flock(libc::LOCK_UN)
+        sys::unlock(self.as_raw_handle())
     }
 }
 
@@ -0,0 +1,53 @@
+//! # Open file description locks
+//!
+//! Classic POSIX `fcntl` record locks (see [`crate::record`]) are associated with a
+//! `(process, inode)` pair: two threads in the same process, or a `dup`'d descriptor,
+//! share one lock, and closing *any* descriptor to the file releases it. Open file
+//! description locks (`F_OFD_SETLK`/`F_OFD_SETLKW`) fix this by tying the lock to the
+//! open file description itself, so it's independent of other descriptors referring
+//! to the same file.
+//!
+//! This is a Linux-specific extension; the trait is only available when building
+//! for `target_os = "linux"`.
+use std::io;
+use std::os::fd::AsRawFd;
+
+use crate::sys;
+
+/// Adds whole-file open file description locking (`F_OFD_SETLK`/`F_OFD_SETLKW`) to a
+/// file descriptor. See the [module docs](self) for how this differs from
+/// [`crate::record::RecordLock`].
+pub trait OfdLock: AsRawFd {
+    /// Blocks until an OFD lock can be taken.
+    ///
+    /// # Arguments
+    ///
+    /// * `exclusive` - Takes a write lock (`F_WRLCK`) when `true`, a read lock (`F_RDLCK`) otherwise.
+    ///
+    /// # Errors
+    ///
+    /// If the lock operation fails, an `io::Error` is returned.
+    fn lock_ofd(&self, exclusive: bool) -> io::Result<()> {
+        sys::lock_ofd(self.as_raw_fd(), exclusive, true)
+    }
+
+    /// Tries to take an OFD lock without blocking.
+    ///
+    /// # Errors
+    ///
+    /// If the lock operation fails or the lock is not immediately available, an `io::Error` is returned.
+    fn try_lock_ofd(&self, exclusive: bool) -> io::Result<()> {
+        sys::lock_ofd(self.as_raw_fd(), exclusive, false)
+    }
+
+    /// Releases an OFD lock held by this open file description.
+    ///
+    /// # Errors
+    ///
+    /// If the unlock operation fails, an `io::Error` is returned.
+    fn unlock_ofd(&self) -> io::Result<()> {
+        sys::unlock_ofd(self.as_raw_fd())
+    }
+}
+
+impl OfdLock for std::fs::File {}
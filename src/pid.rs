@@ -40,17 +40,25 @@
 //! is automatically cleaned up when it goes out of scope. The file
 //! is unlocked and removed from the file system.
 //!
+//! ## Stale pidfiles
+//!
+//! `Pid::new` always truncates and takes over the file, which can mask a crashed
+//! process's stale pidfile. Use [`Pid::acquire`] instead when you want to detect
+//! that another process still holds the lock (it returns the holder's pid) while
+//! still adopting a pidfile nobody is actually holding the lock on. [`Pid::read`]
+//! lets you inspect an existing pidfile without taking it over at all.
+//!
 //! ## Notes
 //!
 //! - The `Pid` crate uses the standard library's file I/O and process ID functionality.
 //! - Ensure that the target directory has proper write permissions for creating and manipulating PID files.
 //! - If the PID file cannot be opened, locked, or written, an `std::io::Error` will be returned.
-use std::fmt::Display;
-use std::fs::File;
+use std::fmt::{self, Display};
+use std::fs::{File, OpenOptions};
 use std::io;
-use std::io::Write;
+use std::io::{Read, Seek, SeekFrom, Write};
 
-use crate::FdLock;
+use crate::{FdLock, TryLockError};
 
 /// Represents a PID (Process ID) file.
 ///
@@ -97,11 +105,206 @@ impl Pid {
             file,
         })
     }
+
+    /// Reads the process id stored in an existing pidfile, without taking it over.
+    ///
+    /// Tries a non-blocking shared lock while reading so a concurrent writer can't
+    /// tear the contents. The pidfile's whole point is that the process it names
+    /// may well be running and holding an exclusive lock on it, so `WouldBlock` is
+    /// not an error here: the contents are read anyway, lock or no lock.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path where the PID file is stored.
+    /// * `name` - The name of the PID file (without the extension).
+    ///
+    /// # Errors
+    ///
+    /// Returns an `std::io::Error` if the file exists but cannot be opened, or the
+    /// lock attempt fails for a reason other than the lock being held.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use filelock_rs::pid::Pid;
+    ///
+    /// if let Some(pid) = Pid::read("/var/run", "my_program").unwrap() {
+    ///     println!("Existing PID file holds: {}", pid);
+    /// }
+    /// ```
+    pub fn read<T: Display>(path: T, name: T) -> io::Result<Option<u32>> {
+        let file_path = format!("{path}/{name}.pid");
+        let mut file = match File::open(&file_path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+
+        // Fully qualified: `std::fs::File` has its own inherent `try_lock_shared`/`unlock`
+        // (with a different error type) that would otherwise shadow `FdLock`'s.
+        match FdLock::try_lock_shared(&file) {
+            Ok(()) => {
+                let mut contents = String::new();
+                file.read_to_string(&mut contents)?;
+                FdLock::unlock(&file)?;
+                Ok(contents.trim().parse().ok())
+            }
+            Err(TryLockError::WouldBlock) => {
+                let mut contents = String::new();
+                file.read_to_string(&mut contents)?;
+                Ok(contents.trim().parse().ok())
+            }
+            Err(TryLockError::Io(err)) => Err(err),
+        }
+    }
+
+    /// Creates or adopts the pidfile at `path/name.pid`.
+    ///
+    /// Unlike [`Pid::new`], this tolerates a pidfile left behind by a crashed
+    /// process: if the file exists but nothing currently holds its lock, it is
+    /// truncated and rewritten with the current process id. If another process
+    /// already holds the lock, this returns [`AcquireError::AlreadyRunning`] with
+    /// that process's pid instead of failing opaquely. This relies on `try_lock_exclusive`
+    /// reporting [`TryLockError::WouldBlock`] for a held lock, which holds on both the
+    /// Unix and Windows backends.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path where the PID file will be stored.
+    /// * `name` - The name of the PID file (without the extension).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AcquireError::AlreadyRunning`] if another process holds the lock and
+    /// the file holds its pid, or [`AcquireError::Io`] if the file cannot be opened,
+    /// locked, or written. If the lock is held but the file's contents don't parse as
+    /// a pid, the error is [`AcquireError::Io`] of kind [`io::ErrorKind::WouldBlock`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use filelock_rs::pid::{AcquireError, Pid};
+    ///
+    /// match Pid::acquire("/var/run", "my_program") {
+    ///     Ok(pid) => println!("Acquired PID file. Process ID: {}", pid.process_id),
+    ///     Err(AcquireError::AlreadyRunning(pid)) => eprintln!("Already running as PID {pid}"),
+    ///     Err(AcquireError::Io(err)) => eprintln!("Failed to acquire PID file: {err}"),
+    /// }
+    /// ```
+    pub fn acquire<T: Display>(path: T, name: T) -> Result<Self, AcquireError> {
+        let pid = std::process::id();
+        let file_path = format!("{path}/{name}.pid");
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&file_path)?;
+
+        match FdLock::try_lock_exclusive(&file) {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                let mut contents = String::new();
+                file.read_to_string(&mut contents)?;
+                if let Ok(holder_pid) = contents.trim().parse() {
+                    return Err(AcquireError::AlreadyRunning(holder_pid));
+                }
+                return Err(AcquireError::Io(io::Error::from(TryLockError::WouldBlock)));
+            }
+            Err(TryLockError::Io(err)) => return Err(AcquireError::Io(err)),
+        }
+
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(format!("{pid}").as_bytes())?;
+        Ok(Self {
+            process_id: pid,
+            file_path,
+            file,
+        })
+    }
+}
+
+/// Error returned by [`Pid::acquire`].
+#[derive(Debug)]
+pub enum AcquireError {
+    /// Another process already holds the pidfile's lock, under this pid.
+    AlreadyRunning(u32),
+    /// Some other I/O error occurred while opening, locking, or writing the pidfile.
+    Io(io::Error),
+}
+
+impl Display for AcquireError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AcquireError::AlreadyRunning(pid) => write!(f, "already running as pid {pid}"),
+            AcquireError::Io(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for AcquireError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AcquireError::AlreadyRunning(_) => None,
+            AcquireError::Io(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for AcquireError {
+    fn from(err: io::Error) -> Self {
+        AcquireError::Io(err)
+    }
 }
 
 impl Drop for Pid {
     fn drop(&mut self) {
-        self.file.unlock().unwrap();
+        FdLock::unlock(&self.file).unwrap();
         std::fs::remove_file(self.file_path.clone()).unwrap();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn temp_dir(name: &str) -> String {
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+        let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let dir = std::env::temp_dir().join(format!("filelock-rs-test-{}-{id}-{name}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn acquire_adopts_a_stale_pidfile() {
+        let dir = temp_dir("pid-stale");
+        let pid_path = format!("{dir}/my_program.pid");
+        // Nothing holds a lock on this: it's a pidfile left behind by a crashed process.
+        std::fs::write(&pid_path, "999999").unwrap();
+
+        let pid = Pid::acquire(dir.as_str(), "my_program").unwrap();
+        assert_eq!(pid.process_id, std::process::id());
+        assert_eq!(std::fs::read_to_string(&pid_path).unwrap(), pid.process_id.to_string());
+
+        drop(pid);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn acquire_reports_already_running_while_the_lock_is_held() {
+        let dir = temp_dir("pid-running");
+        let first = Pid::acquire(dir.as_str(), "my_program").unwrap();
+
+        match Pid::acquire(dir.as_str(), "my_program") {
+            Err(AcquireError::AlreadyRunning(pid)) => assert_eq!(pid, first.process_id),
+            Ok(_) => panic!("expected AlreadyRunning, got Ok"),
+            Err(other) => panic!("expected AlreadyRunning, got {other}"),
+        }
+
+        drop(first);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,225 @@
+//! # ProcessLocker
+//!
+//! `ProcessLocker` is a higher-level reader/writer lock built on top of a single
+//! lock file, using the non-blocking [`RecordLock`] primitives so that acquiring a
+//! lock never blocks the whole process — callers that get `WouldBlock` are expected
+//! to retry (with backoff) at a higher level.
+//!
+//! Besides the OS-level lock, it tracks the UNIX timestamp at which each currently
+//! held shared lock was taken, so long-running readers that might be blocking
+//! cleanup can be detected via [`ProcessLocker::oldest_shared_lock`].
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::record::RecordLock;
+
+fn unix_timestamp() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Which kind of lock, if any, `ProcessLocker` currently holds on the underlying file.
+enum LockKind {
+    None,
+    Shared,
+    Exclusive,
+}
+
+struct State {
+    file: File,
+    kind: LockKind,
+    writers: u32,
+    /// Maps a guard id to the UNIX timestamp at which its shared lock was taken.
+    shared_locks: HashMap<u64, i64>,
+    next_guard_id: u64,
+}
+
+/// A reader/writer lock over a single lock file, handing out [`SharedGuard`] and
+/// [`ExclusiveGuard`] RAII guards. See the [module docs](self) for details.
+pub struct ProcessLocker {
+    state: Mutex<State>,
+}
+
+impl ProcessLocker {
+    /// Opens (creating if necessary) the lock file at `path` and wraps it in a new `ProcessLocker`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `io::Error` if the file cannot be opened.
+    pub fn new<P: AsRef<Path>>(path: P) -> io::Result<Arc<Self>> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        Ok(Arc::new(Self {
+            state: Mutex::new(State {
+                file,
+                kind: LockKind::None,
+                writers: 0,
+                shared_locks: HashMap::new(),
+                next_guard_id: 0,
+            }),
+        }))
+    }
+
+    /// Tries to take a shared lock, without blocking.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `io::Error` of kind [`io::ErrorKind::WouldBlock`] if the lock is
+    /// currently held exclusively, or whatever error the underlying `fcntl` call produced.
+    pub fn try_shared(self: &Arc<Self>) -> io::Result<SharedGuard> {
+        let mut state = self.state.lock().unwrap();
+        match state.kind {
+            LockKind::None => {
+                state.file.lock_range(0, 0, false, false)?;
+                state.kind = LockKind::Shared;
+            }
+            LockKind::Exclusive => {
+                return Err(io::Error::new(
+                    io::ErrorKind::WouldBlock,
+                    "process lock is held exclusively",
+                ));
+            }
+            LockKind::Shared => {}
+        }
+
+        let id = state.next_guard_id;
+        state.next_guard_id += 1;
+        state.shared_locks.insert(id, unix_timestamp());
+        Ok(SharedGuard {
+            locker: Arc::clone(self),
+            id,
+        })
+    }
+
+    /// Tries to take an exclusive lock, without blocking.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `io::Error` of kind [`io::ErrorKind::WouldBlock`] if the lock is
+    /// currently held (shared or exclusive), or whatever error the underlying `fcntl` call produced.
+    pub fn try_exclusive(self: &Arc<Self>) -> io::Result<ExclusiveGuard> {
+        let mut state = self.state.lock().unwrap();
+        match state.kind {
+            LockKind::None => {
+                state.file.lock_range(0, 0, true, false)?;
+                state.kind = LockKind::Exclusive;
+            }
+            LockKind::Shared | LockKind::Exclusive => {
+                return Err(io::Error::new(
+                    io::ErrorKind::WouldBlock,
+                    "process lock is already held",
+                ));
+            }
+        }
+
+        state.writers += 1;
+        Ok(ExclusiveGuard {
+            locker: Arc::clone(self),
+        })
+    }
+
+    /// Returns the oldest UNIX timestamp among currently live shared locks, if any.
+    pub fn oldest_shared_lock(&self) -> Option<i64> {
+        let state = self.state.lock().unwrap();
+        state.shared_locks.values().copied().min()
+    }
+}
+
+/// RAII guard for a shared lock taken through [`ProcessLocker::try_shared`].
+///
+/// Dropping the last outstanding `SharedGuard` releases the underlying file lock;
+/// dropping any other just downgrades the reader count by one.
+pub struct SharedGuard {
+    locker: Arc<ProcessLocker>,
+    id: u64,
+}
+
+impl Drop for SharedGuard {
+    fn drop(&mut self) {
+        let mut state = self.locker.state.lock().unwrap();
+        state.shared_locks.remove(&self.id);
+        if state.shared_locks.is_empty() && state.writers == 0 {
+            let _ = state.file.unlock_range(0, 0);
+            state.kind = LockKind::None;
+        }
+    }
+}
+
+/// RAII guard for an exclusive lock taken through [`ProcessLocker::try_exclusive`].
+pub struct ExclusiveGuard {
+    locker: Arc<ProcessLocker>,
+}
+
+impl Drop for ExclusiveGuard {
+    fn drop(&mut self) {
+        let mut state = self.locker.state.lock().unwrap();
+        state.writers -= 1;
+        if state.writers == 0 && state.shared_locks.is_empty() {
+            let _ = state.file.unlock_range(0, 0);
+            state.kind = LockKind::None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+        let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+        std::env::temp_dir().join(format!("filelock-rs-test-{}-{id}-{name}", std::process::id()))
+    }
+
+    #[test]
+    fn oldest_shared_lock_tracks_live_readers() {
+        let path = temp_path("process-locker-oldest");
+        let locker = ProcessLocker::new(&path).unwrap();
+        assert_eq!(locker.oldest_shared_lock(), None);
+
+        let guard = locker.try_shared().unwrap();
+        assert!(locker.oldest_shared_lock().is_some());
+
+        drop(guard);
+        assert_eq!(locker.oldest_shared_lock(), None);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn exclusive_lock_excludes_shared_and_vice_versa() {
+        let path = temp_path("process-locker-exclusion");
+        let locker = ProcessLocker::new(&path).unwrap();
+
+        let shared = locker.try_shared().unwrap();
+        match locker.try_exclusive() {
+            Err(err) => assert_eq!(err.kind(), io::ErrorKind::WouldBlock),
+            Ok(_) => panic!("expected try_exclusive to fail while a shared lock is held"),
+        }
+        drop(shared);
+
+        let exclusive = locker.try_exclusive().unwrap();
+        match locker.try_shared() {
+            Err(err) => assert_eq!(err.kind(), io::ErrorKind::WouldBlock),
+            Ok(_) => panic!("expected try_shared to fail while an exclusive lock is held"),
+        }
+        drop(exclusive);
+
+        // Fully released: both kinds can be acquired again.
+        locker.try_shared().unwrap();
+        locker.try_exclusive().unwrap();
+
+        std::fs::remove_file(&path).ok();
+    }
+}
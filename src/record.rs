@@ -0,0 +1,55 @@
+//! # Record locking
+//!
+//! `flock` (see [`crate::FdLock`]) always locks the whole file. This module adds
+//! byte-range locking on top of POSIX `fcntl(F_SETLK`/`F_SETLKW)`, for coordination
+//! patterns — databases, segmented logs — that only need to lock part of a file.
+//!
+//! ## Notes
+//!
+//! POSIX record locks are owned per-process, not per file descriptor: closing *any*
+//! descriptor your process holds on the file drops *all* record locks your process
+//! holds on it, and they don't stack across descriptors in the same process. This is
+//! a different ownership model from the whole-file `flock` locks in [`crate::FdLock`],
+//! which are owned by the open file description.
+use std::io;
+use std::os::fd::AsRawFd;
+
+use crate::sys;
+
+/// Adds byte-range record locking (via `fcntl`) to a file descriptor.
+///
+/// See the [module docs](self) for the process-wide ownership caveat that sets
+/// these locks apart from [`crate::FdLock`]'s whole-file `flock` locks.
+pub trait RecordLock: AsRawFd {
+    /// Locks the byte range `[offset, offset + len)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - Start of the byte range to lock.
+    /// * `len` - Length of the byte range to lock. A `len` of `0` locks from `offset`
+    ///   through the end of the file, however far the file grows.
+    /// * `exclusive` - Takes a write lock (`F_WRLCK`) when `true`, a read lock (`F_RDLCK`) otherwise.
+    /// * `blocking` - Uses `F_SETLKW` when `true`, `F_SETLK` otherwise.
+    ///
+    /// # Errors
+    ///
+    /// If the lock operation fails (or, for the non-blocking case, the range is
+    /// already locked by another process), an `io::Error` is returned.
+    fn lock_range(&self, offset: u64, len: u64, exclusive: bool, blocking: bool) -> io::Result<()> {
+        sys::lock_range(self.as_raw_fd(), offset, len, exclusive, blocking)
+    }
+
+    /// Unlocks the byte range `[offset, offset + len)`.
+    ///
+    /// As with [`RecordLock::lock_range`], a `len` of `0` extends the range through
+    /// the end of the file.
+    ///
+    /// # Errors
+    ///
+    /// If the unlock operation fails, an `io::Error` is returned.
+    fn unlock_range(&self, offset: u64, len: u64) -> io::Result<()> {
+        sys::unlock_range(self.as_raw_fd(), offset, len)
+    }
+}
+
+impl RecordLock for std::fs::File {}
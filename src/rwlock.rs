@@ -0,0 +1,228 @@
+//! # RwLock
+//!
+//! `FdRwLock` wraps a type that implements [`FdLock`] and provides RAII-style guards
+//! that automatically unlock the underlying file when they go out of scope, instead
+//! of requiring callers to remember to call `unlock()` themselves.
+//!
+//! ## Examples
+//!
+//! ```no_run
+//! use filelock_rs::rwlock::FdRwLock;
+//! use std::fs::File;
+//!
+//! let file = File::open("data.txt").expect("Failed to open file");
+//! let mut lock = FdRwLock::new(file);
+//!
+//! {
+//!     let guard = lock.read().expect("Failed to take shared lock");
+//!     // read from `*guard` here
+//! } // lock released here
+//!
+//! {
+//!     let mut guard = lock.write().expect("Failed to take exclusive lock");
+//!     // read/write `*guard` here
+//! } // lock released here
+//! ```
+use std::io;
+use std::ops::{Deref, DerefMut};
+use std::sync::Mutex;
+
+use crate::FdLock;
+
+/// Wraps a value implementing [`FdLock`] and hands out RAII guards that hold the
+/// shared/exclusive file lock for as long as they're alive.
+///
+/// `flock` itself has no concept of multiple shared holders on one descriptor: a
+/// second `read()` call doesn't "add" to the lock, and unlocking is all-or-nothing.
+/// `FdRwLock` papers over that by refcounting outstanding [`FdReadGuard`]s itself, so
+/// the underlying lock is only released once the last one is dropped.
+pub struct FdRwLock<T: FdLock> {
+    inner: T,
+    readers: Mutex<u32>,
+}
+
+impl<T: FdLock> FdRwLock<T> {
+    /// Wraps `inner` so it can be locked through RAII guards.
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            readers: Mutex::new(0),
+        }
+    }
+
+    /// Blocks until a shared lock can be taken, then returns a guard holding it.
+    ///
+    /// # Errors
+    ///
+    /// If the lock operation fails, an `io::Error` is returned.
+    pub fn read(&self) -> io::Result<FdReadGuard<'_, T>> {
+        // Claim a reader slot and release the bookkeeping mutex *before* the
+        // (possibly blocking) syscall, so a concurrent `try_read` never blocks on
+        // this mutex for as long as this call blocks on the OS lock.
+        let is_first_reader = {
+            let mut readers = self.readers.lock().unwrap();
+            *readers += 1;
+            *readers == 1
+        };
+        if is_first_reader {
+            if let Err(err) = self.inner.lock_shared() {
+                *self.readers.lock().unwrap() -= 1;
+                return Err(err);
+            }
+        }
+        Ok(FdReadGuard {
+            inner: &self.inner,
+            readers: &self.readers,
+        })
+    }
+
+    /// Blocks until an exclusive lock can be taken, then returns a guard holding it.
+    ///
+    /// # Errors
+    ///
+    /// If the lock operation fails, an `io::Error` is returned.
+    pub fn write(&mut self) -> io::Result<FdWriteGuard<'_, T>> {
+        self.inner.lock_exclusive()?;
+        Ok(FdWriteGuard { inner: &mut self.inner })
+    }
+
+    /// Tries to take a shared lock without blocking, returning a guard holding it.
+    ///
+    /// # Errors
+    ///
+    /// If the lock operation fails or the lock is not immediately available, an `io::Error` is returned.
+    pub fn try_read(&self) -> io::Result<FdReadGuard<'_, T>> {
+        // See `read`: release the bookkeeping mutex before the syscall so this
+        // method's own non-blocking contract holds even while another thread is
+        // blocked inside `read`'s `lock_shared` call.
+        let is_first_reader = {
+            let mut readers = self.readers.lock().unwrap();
+            *readers += 1;
+            *readers == 1
+        };
+        if is_first_reader {
+            if let Err(err) = self.inner.try_lock_shared() {
+                *self.readers.lock().unwrap() -= 1;
+                return Err(err.into());
+            }
+        }
+        Ok(FdReadGuard {
+            inner: &self.inner,
+            readers: &self.readers,
+        })
+    }
+
+    /// Tries to take an exclusive lock without blocking, returning a guard holding it.
+    ///
+    /// # Errors
+    ///
+    /// If the lock operation fails or the lock is not immediately available, an `io::Error` is returned.
+    pub fn try_write(&mut self) -> io::Result<FdWriteGuard<'_, T>> {
+        self.inner.try_lock_exclusive()?;
+        Ok(FdWriteGuard { inner: &mut self.inner })
+    }
+}
+
+/// RAII guard holding a shared lock on a [`FdRwLock`]'s inner value.
+///
+/// Multiple `FdReadGuard`s can be outstanding at once; the underlying file lock is
+/// only released once the last one is dropped.
+pub struct FdReadGuard<'a, T: FdLock> {
+    inner: &'a T,
+    readers: &'a Mutex<u32>,
+}
+
+impl<'a, T: FdLock> Deref for FdReadGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.inner
+    }
+}
+
+impl<'a, T: FdLock> Drop for FdReadGuard<'a, T> {
+    fn drop(&mut self) {
+        let mut readers = self.readers.lock().unwrap();
+        *readers -= 1;
+        if *readers == 0 {
+            let _ = self.inner.unlock();
+        }
+    }
+}
+
+/// RAII guard holding an exclusive lock on a [`FdRwLock`]'s inner value.
+///
+/// The lock is released when the guard is dropped.
+pub struct FdWriteGuard<'a, T: FdLock> {
+    inner: &'a mut T,
+}
+
+impl<'a, T: FdLock> Deref for FdWriteGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.inner
+    }
+}
+
+impl<'a, T: FdLock> DerefMut for FdWriteGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.inner
+    }
+}
+
+impl<'a, T: FdLock> Drop for FdWriteGuard<'a, T> {
+    fn drop(&mut self) {
+        let _ = self.inner.unlock();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TryLockError;
+    use std::fs::{File, OpenOptions};
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn temp_file() -> (std::path::PathBuf, File) {
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+        let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let path = std::env::temp_dir().join(format!(
+            "filelock-rs-test-{}-{id}-rwlock-refcount",
+            std::process::id()
+        ));
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)
+            .unwrap();
+        (path, file)
+    }
+
+    #[test]
+    fn reader_refcount_only_releases_the_lock_after_the_last_guard_drops() {
+        let (path, file) = temp_file();
+        let lock = FdRwLock::new(file);
+        // A second, independent descriptor onto the same file: `flock` is scoped to
+        // the open file description, so this is the only way to observe from here
+        // whether `lock`'s shared lock is still held.
+        let other = OpenOptions::new().read(true).write(true).open(&path).unwrap();
+
+        let r1 = lock.read().unwrap();
+        let r2 = lock.read().unwrap();
+        assert!(matches!(FdLock::try_lock_exclusive(&other), Err(TryLockError::WouldBlock)));
+
+        drop(r1);
+        // One reader is still outstanding: the shared lock must still be held.
+        assert!(matches!(FdLock::try_lock_exclusive(&other), Err(TryLockError::WouldBlock)));
+
+        drop(r2);
+        // Last reader gone: the lock is fully released now.
+        FdLock::try_lock_exclusive(&other).unwrap();
+        FdLock::unlock(&other).unwrap();
+
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,16 @@
+//! Platform-specific locking backends.
+//!
+//! Each backend exposes the same set of free functions
+//! (`lock_shared`, `lock_exclusive`, `try_lock_shared`, `try_lock_exclusive`, `unlock`)
+//! operating on the platform's native file descriptor/handle type, so
+//! [`crate::FdLock`] can stay a thin, platform-agnostic wrapper around them.
+
+#[cfg(unix)]
+mod unix;
+#[cfg(unix)]
+pub(crate) use unix::*;
+
+#[cfg(windows)]
+mod windows;
+#[cfg(windows)]
+pub(crate) use windows::*;
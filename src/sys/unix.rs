@@ -0,0 +1,138 @@
+//! Unix locking backend, built on `flock` (or `fcntl`-emulated `flock` on Solaris).
+
+use std::io;
+use std::os::fd::RawFd;
+
+/// FdLock Operation type.
+pub(crate) type Operation = libc::c_int;
+
+/// Place a shared lock. More than one process may hold a shared lock for a given file at a given time.
+const LOCK_SH: Operation = libc::LOCK_SH;
+/// Place an exclusive lock. Only one process may hold an exclusive lock for a given file at a given time.
+const LOCK_EX: Operation = libc::LOCK_EX;
+/// Remove an existing lock held by this process.
+const LOCK_UN: Operation = libc::LOCK_UN;
+
+#[cfg(not(target_os = "solaris"))]
+fn flock(fd: RawFd, operation: Operation) -> io::Result<()> {
+    let ret = unsafe { libc::flock(fd, operation) };
+    if ret < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "solaris")]
+fn flock(fd: RawFd, operation: Operation) -> io::Result<()> {
+    // Solaris lacks flock(), so try to emulate using fcntl()
+    let mut flock = libc::flock {
+        l_type: 0,
+        l_whence: 0,
+        l_start: 0,
+        l_len: 0,
+        l_sysid: 0,
+        l_pid: 0,
+        l_pad: [0, 0, 0, 0],
+    };
+    flock.l_type = if operation & LOCK_UN != 0 {
+        LOCK_UN
+    } else if operation & LOCK_EX != 0 {
+        libc::F_WRLCK
+    } else if operation & LOCK_SH != 0 {
+        libc::F_RDLCK
+    } else {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            "unexpected flock() operation",
+        ));
+    };
+
+    let cmd = if (operation & libc::LOCK_NB) != 0 {
+        libc::F_SETLK
+    } else {
+        libc::F_SETLKW
+    };
+
+    let ret = unsafe { libc::fcntl(fd, cmd, &flock) };
+    if ret < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+pub(crate) fn lock_shared(fd: RawFd) -> io::Result<()> {
+    flock(fd, LOCK_SH)
+}
+
+pub(crate) fn lock_exclusive(fd: RawFd) -> io::Result<()> {
+    flock(fd, LOCK_EX)
+}
+
+pub(crate) fn try_lock_shared(fd: RawFd) -> io::Result<()> {
+    flock(fd, LOCK_SH | libc::LOCK_NB)
+}
+
+pub(crate) fn try_lock_exclusive(fd: RawFd) -> io::Result<()> {
+    flock(fd, LOCK_EX | libc::LOCK_NB)
+}
+
+pub(crate) fn unlock(fd: RawFd) -> io::Result<()> {
+    flock(fd, LOCK_UN)
+}
+
+/// Builds an `fcntl` record lock/unlock request covering `[offset, offset + len)` and
+/// submits it with `F_SETLK` (non-blocking) or `F_SETLKW` (blocking).
+fn fcntl_lock(fd: RawFd, l_type: libc::c_int, offset: u64, len: u64, blocking: bool) -> io::Result<()> {
+    let mut flock: libc::flock = unsafe { std::mem::zeroed() };
+    flock.l_type = l_type as _;
+    flock.l_whence = libc::SEEK_SET as _;
+    flock.l_start = offset as libc::off_t;
+    flock.l_len = len as libc::off_t;
+
+    let cmd = if blocking { libc::F_SETLKW } else { libc::F_SETLK };
+    let ret = unsafe { libc::fcntl(fd, cmd, &flock) };
+    if ret < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+pub(crate) fn lock_range(fd: RawFd, offset: u64, len: u64, exclusive: bool, blocking: bool) -> io::Result<()> {
+    let l_type = if exclusive { libc::F_WRLCK } else { libc::F_RDLCK };
+    fcntl_lock(fd, l_type, offset, len, blocking)
+}
+
+pub(crate) fn unlock_range(fd: RawFd, offset: u64, len: u64) -> io::Result<()> {
+    // Unlocking is never blocking, so the flag here is moot.
+    fcntl_lock(fd, libc::F_UNLCK, offset, len, false)
+}
+
+/// Whole-file open file description lock/unlock request, submitted with
+/// `F_OFD_SETLK` (non-blocking) or `F_OFD_SETLKW` (blocking).
+#[cfg(target_os = "linux")]
+fn fcntl_ofd_lock(fd: RawFd, l_type: libc::c_int, blocking: bool) -> io::Result<()> {
+    let mut flock: libc::flock = unsafe { std::mem::zeroed() };
+    flock.l_type = l_type as _;
+    flock.l_whence = libc::SEEK_SET as _;
+    flock.l_start = 0;
+    flock.l_len = 0;
+
+    let cmd = if blocking { libc::F_OFD_SETLKW } else { libc::F_OFD_SETLK };
+    let ret = unsafe { libc::fcntl(fd, cmd, &flock) };
+    if ret < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn lock_ofd(fd: RawFd, exclusive: bool, blocking: bool) -> io::Result<()> {
+    let l_type = if exclusive { libc::F_WRLCK } else { libc::F_RDLCK };
+    fcntl_ofd_lock(fd, l_type, blocking)
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn unlock_ofd(fd: RawFd) -> io::Result<()> {
+    fcntl_ofd_lock(fd, libc::F_UNLCK, false)
+}
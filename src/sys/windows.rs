@@ -0,0 +1,68 @@
+//! Windows locking backend, built on `LockFileEx`/`UnlockFile`.
+
+use std::io;
+use std::mem;
+use std::os::windows::io::RawHandle;
+
+use windows_sys::Win32::Foundation::{ERROR_IO_PENDING, ERROR_LOCK_VIOLATION, HANDLE};
+use windows_sys::Win32::Storage::FileSystem::{
+    LockFileEx, UnlockFile, LOCKFILE_EXCLUSIVE_LOCK, LOCKFILE_FAIL_IMMEDIATELY,
+};
+use windows_sys::Win32::System::IO::OVERLAPPED;
+
+/// Fetches the last OS error, remapping the Win32 codes `LockFileEx` raises for an
+/// already-held `LOCKFILE_FAIL_IMMEDIATELY` lock (`ERROR_LOCK_VIOLATION`,
+/// `ERROR_IO_PENDING`) to `ErrorKind::WouldBlock`, the way std already does for
+/// Unix's `EWOULDBLOCK`/`EAGAIN`. Without this, `TryLockError::from` never produces
+/// `TryLockError::WouldBlock` on Windows.
+fn last_os_error() -> io::Error {
+    let err = io::Error::last_os_error();
+    match err.raw_os_error() {
+        Some(code) if code as u32 == ERROR_LOCK_VIOLATION || code as u32 == ERROR_IO_PENDING => {
+            io::Error::new(io::ErrorKind::WouldBlock, err)
+        }
+        _ => err,
+    }
+}
+
+fn lock(handle: RawHandle, flags: u32) -> io::Result<()> {
+    let mut overlapped: OVERLAPPED = unsafe { mem::zeroed() };
+    let ret = unsafe {
+        LockFileEx(
+            handle as HANDLE,
+            flags,
+            0,
+            u32::MAX,
+            u32::MAX,
+            &mut overlapped,
+        )
+    };
+    if ret == 0 {
+        return Err(last_os_error());
+    }
+    Ok(())
+}
+
+pub(crate) fn lock_shared(handle: RawHandle) -> io::Result<()> {
+    lock(handle, 0)
+}
+
+pub(crate) fn lock_exclusive(handle: RawHandle) -> io::Result<()> {
+    lock(handle, LOCKFILE_EXCLUSIVE_LOCK)
+}
+
+pub(crate) fn try_lock_shared(handle: RawHandle) -> io::Result<()> {
+    lock(handle, LOCKFILE_FAIL_IMMEDIATELY)
+}
+
+pub(crate) fn try_lock_exclusive(handle: RawHandle) -> io::Result<()> {
+    lock(handle, LOCKFILE_EXCLUSIVE_LOCK | LOCKFILE_FAIL_IMMEDIATELY)
+}
+
+pub(crate) fn unlock(handle: RawHandle) -> io::Result<()> {
+    let ret = unsafe { UnlockFile(handle as HANDLE, 0, 0, u32::MAX, u32::MAX) };
+    if ret == 0 {
+        return Err(last_os_error());
+    }
+    Ok(())
+}